@@ -1,9 +1,12 @@
 //! Methods to connect to a WebSocket as a client.
 
 use std::{
-    io::{Read, Write},
+    io::{self, Read, Write},
     net::{SocketAddr, TcpStream, ToSocketAddrs},
     result::Result as StdResult,
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
 };
 
 use cfg_if::cfg_if;
@@ -33,6 +36,62 @@ cfg_if! {
 mod plain;
 pub mod tls;
 
+/// Default [`ConnectConfig::connect_timeout`] applied when the caller doesn't set one.
+///
+/// Each candidate address races on its own OS thread doing a *blocking* connect, since `std`
+/// has no portable way to poll a [`TcpStream`] connect for readiness. Rust cannot cancel a
+/// thread blocked in a syscall, so once a faster candidate wins, any slower candidates' threads
+/// keep running until their connect call returns on its own. Without a bound, a black-holed
+/// address can leave such a thread parked for as long as the OS-level connect timeout (which can
+/// be tens of seconds to several minutes), rather than the more modest delay most callers expect
+/// from a "blocking connect" convenience helper. Set [`ConnectConfig::connect_timeout`]
+/// explicitly to `None` to opt back into the OS default instead.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Configuration for establishing the underlying TCP connection in [`connect_with_config`].
+///
+/// This controls the Happy Eyeballs (RFC 8305) address racing that [`connect_with_config`]
+/// performs when a host resolves to more than one address, as well as the timeouts applied to
+/// the connection once a candidate address has been chosen.
+///
+/// Racing candidates each run on their own spawned thread doing a blocking connect; see
+/// [`DEFAULT_CONNECT_TIMEOUT`] for why [`ConnectConfig::connect_timeout`] defaults to `Some` and
+/// why those threads can briefly outlive a successful `connect_with_config` call.
+#[derive(Clone, Copy, Debug)]
+pub struct ConnectConfig {
+    /// How long to wait for a connection attempt to complete before racing the next candidate
+    /// address in parallel. Defaults to 250ms, as recommended by RFC 8305.
+    pub attempt_delay: Duration,
+    /// Overall deadline for establishing a connection, across all candidate addresses. `None`
+    /// (the default) means no overall deadline is enforced; each candidate is then bounded only
+    /// by [`ConnectConfig::connect_timeout`] (or the OS-level connect timeout if that is `None`).
+    pub deadline: Option<Duration>,
+    /// Maximum time to wait for a single candidate's TCP connection to be established. Defaults
+    /// to [`DEFAULT_CONNECT_TIMEOUT`] rather than `None`, so that a candidate racing in the
+    /// background on its own thread can't block indefinitely past the OS-level connect timeout;
+    /// set this to `None` explicitly to opt back into that OS default.
+    pub connect_timeout: Option<Duration>,
+    /// Maximum time to wait for a read to complete once connected. Applied to the underlying
+    /// [`TcpStream`] before the TLS handshake, so it also bounds the handshake itself as well as
+    /// subsequent `WebSocket` reads. `None` (the default) means reads can block indefinitely.
+    pub read_timeout: Option<Duration>,
+    /// Maximum time to wait for a write to complete once connected, analogous to
+    /// [`ConnectConfig::read_timeout`].
+    pub write_timeout: Option<Duration>,
+}
+
+impl Default for ConnectConfig {
+    fn default() -> Self {
+        Self {
+            attempt_delay: Duration::from_millis(250),
+            deadline: None,
+            connect_timeout: Some(DEFAULT_CONNECT_TIMEOUT),
+            read_timeout: None,
+            write_timeout: None,
+        }
+    }
+}
+
 /// Connect to the given WebSocket in blocking mode.
 ///
 /// Uses a websocket configuration passed as an argument to the function. Calling it with `None` is
@@ -48,10 +107,15 @@ pub mod tls;
 /// This function uses `native_tls` to do TLS. If you want to use other TLS libraries,
 /// use `client` instead. There is no need to enable the "tls" feature if you don't call
 /// `connect` since it's the only function that uses native_tls.
+///
+/// `connect_config` controls how the underlying TCP connection is established, such as Happy
+/// Eyeballs address racing and connect/read/write timeouts. Pass [`ConnectConfig::default()`] to
+/// get the same behavior as `connect()`.
 pub fn connect_with_config<Req: IntoClientRequest>(
     request: Req,
     config: Option<WebSocketConfig>,
     max_redirects: u8,
+    connect_config: ConnectConfig,
     #[cfg(any(feature = "use-native-tls", feature = "use-rustls"))] wrapper: &impl TlsWrapper<
         Stream = AutoStream,
     >,
@@ -59,6 +123,7 @@ pub fn connect_with_config<Req: IntoClientRequest>(
     fn try_client_handshake(
         request: Request,
         config: Option<WebSocketConfig>,
+        connect_config: &ConnectConfig,
         wrapper: &impl TlsWrapper<Stream = AutoStream>,
     ) -> Result<(WebSocket<AutoStream>, Response)> {
         let uri = request.uri();
@@ -70,8 +135,8 @@ pub fn connect_with_config<Req: IntoClientRequest>(
             Mode::Tls => 443,
         });
         let addrs = (host, port).to_socket_addrs()?;
-        let mut stream = connect_to_some(addrs.as_slice(), &request.uri(), mode, wrapper)?;
-        NoDelay::set_nodelay(&mut stream, true)?;
+        let stream =
+            connect_to_some(addrs.as_slice(), &request.uri(), mode, connect_config, wrapper)?;
         client_with_config(request, stream, config).map_err(|e| match e {
             HandshakeError::Failure(f) => f,
             HandshakeError::Interrupted(_) => panic!("Bug: blocking handshake not blocked"),
@@ -94,7 +159,7 @@ pub fn connect_with_config<Req: IntoClientRequest>(
     for attempt in 0..(max_redirects + 1) {
         let request = create_request(&parts, &uri);
 
-        match try_client_handshake(request, config, wrapper) {
+        match try_client_handshake(request, config, &connect_config, wrapper) {
             Err(Error::Http(res)) if res.status().is_redirection() && attempt < max_redirects => {
                 if let Some(location) = res.headers().get("Location") {
                     uri = location.to_str()?.parse::<Uri>()?;
@@ -136,9 +201,9 @@ pub fn connect<Req: IntoClientRequest>(
                     use tls::RustlsWrapper as Wrapper;
                 }
             }
-            connect_with_config(request, None, 3, &Wrapper)
+            connect_with_config(request, None, 3, ConnectConfig::default(), &Wrapper::default())
         } else {
-            connect_with_config(request, None, 3)
+            connect_with_config(request, None, 3, ConnectConfig::default())
         }
     }
 }
@@ -160,25 +225,183 @@ pub fn connect_tls<Req: IntoClientRequest>(
     request: Req,
     wrapper: &impl TlsWrapper<Stream = AutoStream>,
 ) -> Result<(WebSocket<AutoStream>, Response)> {
-    connect_with_config(request, None, 3, wrapper)
+    connect_with_config(request, None, 3, ConnectConfig::default(), wrapper)
+}
+
+/// Connect to the given WebSocket in blocking mode, bounding how long the connect and subsequent
+/// reads/writes are allowed to take.
+///
+/// The URL may be either ws:// or wss://. To support wss:// URLs, feature "tls" must be turned on.
+///
+/// This is a convenience wrapper around [`connect_with_config`] for the common case of wanting a
+/// bounded connect without building a full [`ConnectConfig`] by hand. Pass `None` for a timeout to
+/// leave it unbounded.
+pub fn connect_with_timeouts<Req: IntoClientRequest>(
+    request: Req,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+) -> Result<(WebSocket<AutoStream>, Response)> {
+    let connect_config =
+        ConnectConfig { connect_timeout, read_timeout, write_timeout, ..ConnectConfig::default() };
+    cfg_if! {
+        if #[cfg(any(feature = "use-native-tls", feature="use-rustls"))] {
+            cfg_if! {
+                if #[cfg(feature = "use-native-tls")] {
+                    use tls::NativeTlsWrapper as Wrapper;
+                } else if #[cfg(feature = "use-rustls")] {
+                    use tls::RustlsWrapper as Wrapper;
+                }
+            }
+            connect_with_config(request, None, 3, connect_config, &Wrapper::default())
+        } else {
+            connect_with_config(request, None, 3, connect_config)
+        }
+    }
+}
+
+/// Reorder the given addresses for Happy Eyeballs (RFC 8305): interleave the address families,
+/// starting with IPv6, so that a black-holed address of one family doesn't delay trying the
+/// other.
+fn happy_eyeballs_order(addrs: &[SocketAddr]) -> Vec<SocketAddr> {
+    let mut v6 = addrs.iter().copied().filter(SocketAddr::is_ipv6);
+    let mut v4 = addrs.iter().copied().filter(SocketAddr::is_ipv4);
+    let mut ordered = Vec::with_capacity(addrs.len());
+    loop {
+        match (v6.next(), v4.next()) {
+            (Some(a), Some(b)) => ordered.extend([a, b]),
+            (Some(a), None) => ordered.push(a),
+            (None, Some(b)) => ordered.push(b),
+            (None, None) => break,
+        }
+    }
+    ordered
+}
+
+/// Finish establishing a connection after the raw TCP connect has succeeded (or report whether
+/// the failure was due to a connect timeout elapsing): disable Nagle's algorithm, apply the
+/// configured read/write timeouts to the underlying [`TcpStream`] so they also cover the TLS
+/// handshake, and then hand the stream to the [`TlsWrapper`].
+///
+/// Returns `Ok(None)` if disabling Nagle's algorithm or wrapping the stream failed. If the TCP
+/// connect itself failed, returns `Err(true)` when `connect_timeout` elapsed and `Err(false)`
+/// otherwise, so the caller can tell a genuine timeout apart from a refused or unreachable
+/// address.
+fn finish_connection(
+    received: io::Result<(SocketAddr, TcpStream)>,
+    domain: &str,
+    mode: Mode,
+    connect_config: &ConnectConfig,
+    wrapper: &impl TlsWrapper<Stream = AutoStream>,
+) -> StdResult<Option<AutoStream>, bool> {
+    let (addr, mut raw_stream) = match received {
+        Ok(pair) => pair,
+        Err(e) => return Err(e.kind() == io::ErrorKind::TimedOut),
+    };
+    debug!("Connected to {}", addr);
+
+    if NoDelay::set_nodelay(&mut raw_stream, true).is_err() {
+        return Ok(None);
+    }
+    if let Some(timeout) = connect_config.read_timeout {
+        let _ = raw_stream.set_read_timeout(Some(timeout));
+    }
+    if let Some(timeout) = connect_config.write_timeout {
+        let _ = raw_stream.set_write_timeout(Some(timeout));
+    }
+
+    Ok(wrapper.wrap_stream(raw_stream, domain, mode).ok())
 }
 
 fn connect_to_some(
     addrs: &[SocketAddr],
     uri: &Uri,
     mode: Mode,
+    connect_config: &ConnectConfig,
     wrapper: &impl TlsWrapper<Stream = AutoStream>,
 ) -> Result<AutoStream> {
     let domain = uri.host().ok_or_else(|| Error::Url("No host name in the URL".into()))?;
-    for addr in addrs {
-        debug!("Trying to contact {} at {}...", uri, addr);
-        if let Ok(raw_stream) = TcpStream::connect(addr) {
-            if let Ok(stream) = wrapper.wrap_stream(raw_stream, domain, mode) {
-                return Ok(stream);
+    let deadline = connect_config.deadline.map(|d| Instant::now() + d);
+    let mut timed_out = false;
+
+    let ordered = happy_eyeballs_order(addrs);
+    if ordered.is_empty() {
+        return Err(Error::Url(format!("Unable to connect to {}", uri).into()));
+    }
+
+    // Race the candidate addresses against each other: start the next candidate if the previous
+    // one hasn't finished connecting within `attempt_delay`, without cancelling it. The first
+    // attempt to successfully connect (and, if applicable, complete the TLS handshake) wins.
+    let (tx, rx) = mpsc::channel();
+    for addr in ordered {
+        let tx = tx.clone();
+        let uri = uri.clone();
+        let connect_timeout = connect_config.connect_timeout;
+        thread::spawn(move || {
+            debug!("Trying to contact {} at {}...", uri, addr);
+            let result = match connect_timeout {
+                Some(timeout) => TcpStream::connect_timeout(&addr, timeout),
+                None => TcpStream::connect(addr),
+            };
+            let _ = tx.send(result.map(|stream| (addr, stream)));
+        });
+
+        let wait = match deadline {
+            Some(deadline) => {
+                connect_config.attempt_delay.min(deadline.saturating_duration_since(Instant::now()))
+            }
+            None => connect_config.attempt_delay,
+        };
+        if let Ok(received) = rx.recv_timeout(wait) {
+            match finish_connection(received, domain, mode, connect_config, wrapper) {
+                Ok(Some(stream)) => return Ok(stream),
+                Ok(None) => {}
+                Err(to) => timed_out |= to,
+            }
+        }
+        if let Some(deadline) = deadline {
+            if Instant::now() >= deadline {
+                break;
+            }
+        }
+    }
+    drop(tx);
+
+    // All candidates have been started; wait for whichever one finishes next, until the deadline.
+    loop {
+        let received = match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+                match rx.recv_timeout(remaining) {
+                    Ok(received) => received,
+                    Err(_) => break,
+                }
+            }
+            // No deadline: block until the last in-flight attempt reports back instead of
+            // guessing at a "long enough" timeout.
+            None => match rx.recv() {
+                Ok(received) => received,
+                Err(_) => break,
+            },
+        };
+        match finish_connection(received, domain, mode, connect_config, wrapper) {
+            Ok(Some(stream)) => return Ok(stream),
+            Ok(None) => continue,
+            Err(to) => {
+                timed_out |= to;
+                continue;
             }
         }
     }
-    Err(Error::Url(format!("Unable to connect to {}", uri).into()))
+
+    if timed_out {
+        Err(Error::ConnectTimeout)
+    } else {
+        Err(Error::Url(format!("Unable to connect to {}", uri).into()))
+    }
 }
 
 /// Get the mode of the given URL.
@@ -290,3 +513,53 @@ impl<'h, 'b> IntoClientRequest for httparse::Request<'h, 'b> {
         Request::from_httparse(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::happy_eyeballs_order;
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+    fn v4(port: u16) -> SocketAddr {
+        SocketAddr::from((Ipv4Addr::new(127, 0, 0, 1), port))
+    }
+
+    fn v6(port: u16) -> SocketAddr {
+        SocketAddr::from((Ipv6Addr::LOCALHOST, port))
+    }
+
+    #[test]
+    fn interleaves_v6_first_when_mixed() {
+        let addrs = [v4(1), v6(2), v4(3), v6(4)];
+        assert_eq!(happy_eyeballs_order(&addrs), vec![v6(2), v4(1), v6(4), v4(3)]);
+    }
+
+    #[test]
+    fn keeps_relative_order_within_a_family() {
+        let addrs = [v4(1), v4(2), v6(3), v6(4)];
+        assert_eq!(happy_eyeballs_order(&addrs), vec![v6(3), v4(1), v6(4), v4(2)]);
+    }
+
+    #[test]
+    fn leftover_candidates_of_one_family_are_appended() {
+        let addrs = [v6(1), v6(2), v6(3), v4(4)];
+        assert_eq!(happy_eyeballs_order(&addrs), vec![v6(1), v4(4), v6(2), v6(3)]);
+    }
+
+    #[test]
+    fn all_v4_is_unchanged() {
+        let addrs = [v4(1), v4(2)];
+        assert_eq!(happy_eyeballs_order(&addrs), vec![v4(1), v4(2)]);
+    }
+
+    #[test]
+    fn all_v6_is_unchanged() {
+        let addrs = [v6(1), v6(2)];
+        assert_eq!(happy_eyeballs_order(&addrs), vec![v6(1), v6(2)]);
+    }
+
+    #[test]
+    fn empty_input_is_empty_output() {
+        let addrs: [SocketAddr; 0] = [];
+        assert!(happy_eyeballs_order(&addrs).is_empty());
+    }
+}
@@ -10,6 +10,13 @@ pub trait TlsWrapper {
     type Stream;
 
     fn wrap_stream(&self, stream: TcpStream, domain: &str, mode: Mode) -> Result<Self::Stream>;
+
+    fn wrap_stream_nonblocking(
+        &self,
+        stream: TcpStream,
+        domain: &str,
+        mode: Mode,
+    ) -> Result<Self::Stream>;
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -29,4 +36,13 @@ impl TlsWrapper for Wrapper {
             Mode::Tls => Err(Error::Url("TLS support not compiled in.".into())),
         }
     }
+
+    fn wrap_stream_nonblocking(
+        &self,
+        stream: TcpStream,
+        domain: &str,
+        mode: Mode,
+    ) -> Result<Self::Stream> {
+        self.wrap_stream(stream, domain, mode)
+    }
 }
\ No newline at end of file
@@ -92,12 +92,53 @@ pub trait TlsWrapper {
     /// the returned stream. If the given [`Mode`] is [`Mode::Plain`] then the returned stream
     /// should simply forward to the [`TcpStream`] and not apply any TLS.
     fn wrap_stream(&self, stream: TcpStream, domain: &str, mode: Mode) -> Result<Self::Stream>;
+
+    /// Like [`TlsWrapper::wrap_stream`], but for non-blocking streams.
+    ///
+    /// If the handshake would block, this returns [`NonblockingStream::Interrupted`] holding a
+    /// [`MidHandshakeTls`] instead of blocking or panicking, mirroring how
+    /// [`HandshakeError::Interrupted`](crate::handshake::HandshakeError::Interrupted) works for
+    /// the WebSocket handshake itself. Drive it to completion by calling
+    /// [`MidHandshakeTls::handshake`] once the stream is readable/writable again.
+    fn wrap_stream_nonblocking(
+        &self,
+        stream: TcpStream,
+        domain: &str,
+        mode: Mode,
+    ) -> Result<NonblockingStream>;
+}
+
+/// The outcome of a [`TlsWrapper::wrap_stream_nonblocking`] call.
+pub enum NonblockingStream {
+    /// The stream is immediately ready to use.
+    Stream(AutoStream),
+    /// The TLS handshake would have blocked and must be resumed later, via
+    /// [`MidHandshakeTls::handshake`], once the underlying stream is readable/writable again.
+    Interrupted(MidHandshakeTls),
+}
+
+/// An in-progress TLS handshake on a non-blocking stream, returned by
+/// [`TlsWrapper::wrap_stream_nonblocking`] when the handshake did not complete immediately.
+pub enum MidHandshakeTls {
+    /// A `native-tls` handshake that needs to be resumed.
+    #[cfg(feature = "use-native-tls")]
+    NativeTls(native_tls::MidHandshakeTls),
+}
+
+impl MidHandshakeTls {
+    /// Resume a previously interrupted TLS handshake.
+    pub fn handshake(self) -> Result<NonblockingStream> {
+        match self {
+            #[cfg(feature = "use-native-tls")]
+            Self::NativeTls(mid) => mid.handshake(),
+        }
+    }
 }
 
 #[cfg(feature = "use-native-tls")]
 mod native_tls {
     pub use native_tls::TlsStream;
-    use native_tls::{HandshakeError as TlsHandshakeError, TlsConnector};
+    use native_tls::{HandshakeError as TlsHandshakeError, MidHandshakeTlsStream, TlsConnector};
     use std::net::TcpStream;
 
     pub use crate::stream::Stream as StreamSwitcher;
@@ -106,9 +147,23 @@ mod native_tls {
 
     use crate::{error::Result, stream::Mode};
 
-    /// A wrapper around a plain TCP stream that utilizes the `native-tls` crate to apply TLS to it.
-    #[derive(Clone, Copy, Debug)]
-    pub struct Wrapper;
+    /// A wrapper around a plain TCP stream that utilizes the `native-tls` crate to apply TLS to
+    /// it.
+    ///
+    /// By default a fresh [`TlsConnector`] with the platform's defaults is built for every TLS
+    /// connection. Use [`Wrapper::from_connector`] to supply a pre-configured connector instead,
+    /// for example to add client certificates, a custom root store, or to disable certificate
+    /// verification for test servers.
+    #[derive(Clone, Debug, Default)]
+    pub struct Wrapper(Option<TlsConnector>);
+
+    impl Wrapper {
+        /// Create a wrapper that uses the given [`TlsConnector`] instead of building a default one
+        /// for every connection.
+        pub fn from_connector(connector: TlsConnector) -> Self {
+            Self(Some(connector))
+        }
+    }
 
     impl super::TlsWrapper for Wrapper {
         type Stream = super::AutoStream;
@@ -117,7 +172,14 @@ mod native_tls {
             match mode {
                 Mode::Plain => Ok(Self::Stream::NativeTls(StreamSwitcher::Plain(stream))),
                 Mode::Tls => {
-                    let connector = TlsConnector::builder().build()?;
+                    let default_connector;
+                    let connector = match &self.0 {
+                        Some(connector) => connector,
+                        None => {
+                            default_connector = TlsConnector::builder().build()?;
+                            &default_connector
+                        }
+                    };
                     connector
                         .connect(domain, stream)
                         .map_err(|e| match e {
@@ -131,6 +193,60 @@ mod native_tls {
                 }
             }
         }
+
+        fn wrap_stream_nonblocking(
+            &self,
+            stream: TcpStream,
+            domain: &str,
+            mode: Mode,
+        ) -> Result<super::NonblockingStream> {
+            match mode {
+                Mode::Plain => Ok(super::NonblockingStream::Stream(Self::Stream::NativeTls(
+                    StreamSwitcher::Plain(stream),
+                ))),
+                Mode::Tls => {
+                    let default_connector;
+                    let connector = match &self.0 {
+                        Some(connector) => connector,
+                        None => {
+                            default_connector = TlsConnector::builder().build()?;
+                            &default_connector
+                        }
+                    };
+                    match connector.connect(domain, stream) {
+                        Ok(stream) => Ok(super::NonblockingStream::Stream(Self::Stream::NativeTls(
+                            StreamSwitcher::Tls(stream),
+                        ))),
+                        Err(TlsHandshakeError::WouldBlock(mid)) => Ok(
+                            super::NonblockingStream::Interrupted(super::MidHandshakeTls::NativeTls(
+                                MidHandshakeTls(mid),
+                            )),
+                        ),
+                        Err(TlsHandshakeError::Failure(f)) => Err(f.into()),
+                    }
+                }
+            }
+        }
+    }
+
+    /// An in-progress `native-tls` handshake on a non-blocking stream.
+    pub struct MidHandshakeTls(MidHandshakeTlsStream<TcpStream>);
+
+    impl MidHandshakeTls {
+        /// Resume a previously interrupted handshake.
+        pub fn handshake(self) -> Result<super::NonblockingStream> {
+            match self.0.handshake() {
+                Ok(stream) => Ok(super::NonblockingStream::Stream(super::AutoStream::NativeTls(
+                    StreamSwitcher::Tls(stream),
+                ))),
+                Err(TlsHandshakeError::WouldBlock(mid)) => Ok(
+                    super::NonblockingStream::Interrupted(super::MidHandshakeTls::NativeTls(
+                        MidHandshakeTls(mid),
+                    )),
+                ),
+                Err(TlsHandshakeError::Failure(f)) => Err(f.into()),
+            }
+        }
     }
 }
 
@@ -149,8 +265,20 @@ mod rustls {
     use crate::{error::Result, stream::Mode};
 
     /// A wrapper around a plain TCP stream that utilizes the `rustls` crate to apply TLS to it.
-    #[derive(Clone, Copy, Debug)]
-    pub struct Wrapper;
+    ///
+    /// By default a [`ClientConfig`] is built using `webpki_roots` as trust anchors. Use
+    /// [`Wrapper::from_config`] to supply a pre-configured `Arc<ClientConfig>` instead, for
+    /// example to add client certificates, a custom root store, or custom ALPN protocols.
+    #[derive(Clone, Debug, Default)]
+    pub struct Wrapper(Option<Arc<ClientConfig>>);
+
+    impl Wrapper {
+        /// Create a wrapper that uses the given [`ClientConfig`] instead of building a default
+        /// one for every connection.
+        pub fn from_config(config: Arc<ClientConfig>) -> Self {
+            Self(Some(config))
+        }
+    }
 
     impl TlsWrapper for Wrapper {
         type Stream = super::AutoStream;
@@ -159,11 +287,16 @@ mod rustls {
             match mode {
                 Mode::Plain => Ok(Self::Stream::Rustls(StreamSwitcher::Plain(stream))),
                 Mode::Tls => {
-                    let config = {
-                        let mut config = ClientConfig::new();
-                        config.root_store.add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
+                    let config = match &self.0 {
+                        Some(config) => Arc::clone(config),
+                        None => {
+                            let mut config = ClientConfig::new();
+                            config
+                                .root_store
+                                .add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
 
-                        Arc::new(config)
+                            Arc::new(config)
+                        }
                     };
                     let domain = DNSNameRef::try_from_ascii_str(domain)?;
                     let client = ClientSession::new(&config, domain);
@@ -173,5 +306,16 @@ mod rustls {
                 }
             }
         }
+
+        fn wrap_stream_nonblocking(
+            &self,
+            stream: TcpStream,
+            domain: &str,
+            mode: Mode,
+        ) -> Result<super::NonblockingStream> {
+            // `StreamOwned` performs the TLS handshake lazily on the first read/write, so there is
+            // no intermediate state to hand back here: the stream is always immediately ready.
+            self.wrap_stream(stream, domain, mode).map(super::NonblockingStream::Stream)
+        }
     }
 }
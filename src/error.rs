@@ -0,0 +1,117 @@
+//! Error handling.
+
+use std::{borrow::Cow, error::Error as ErrorTrait, fmt, io, result};
+
+use http::{header::ToStrError, uri::InvalidUri, Response};
+
+#[cfg(feature = "use-native-tls")]
+use native_tls::Error as NativeTlsError;
+#[cfg(feature = "use-rustls")]
+use webpki::InvalidDNSNameError;
+
+/// Result type of all Tungstenite library calls.
+pub type Result<T> = result::Result<T, Error>;
+
+/// Possible WebSocket errors.
+#[derive(Debug)]
+pub enum Error {
+    /// WebSocket connection closed normally. This informs you of the close.
+    /// It's not an error as such and nothing bad has happened. If you're
+    /// writing a server, this error should be safe to ignore.
+    ConnectionClosed,
+    /// Trying to work with already closed connection.
+    AlreadyClosed,
+    /// Input-output error. Apart from WouldBlock, these are generally errors with the underlying
+    /// connection and you should probably consider them fatal.
+    Io(io::Error),
+    /// Establishing the underlying TCP connection timed out before any candidate address
+    /// succeeded. Distinct from [`Error::Io`] so that callers can tell a bounded
+    /// [`crate::client::ConnectConfig::connect_timeout`] expiring apart from any other I/O
+    /// failure and decide whether to retry.
+    ConnectTimeout,
+    /// TLS error from the `native-tls` backend.
+    #[cfg(feature = "use-native-tls")]
+    NativeTls(NativeTlsError),
+    /// TLS error from the `rustls` backend: the given domain isn't a valid DNS name.
+    #[cfg(feature = "use-rustls")]
+    InvalidDnsName(InvalidDNSNameError),
+    /// UTF coding error.
+    Utf8,
+    /// Invalid URL.
+    Url(Cow<'static, str>),
+    /// HTTP error.
+    Http(Response<Option<Vec<u8>>>),
+    /// HTTP format error.
+    HttpFormat(http::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ConnectionClosed => write!(f, "Connection closed normally"),
+            Error::AlreadyClosed => write!(f, "Trying to work with closed connection"),
+            Error::Io(err) => write!(f, "IO error: {}", err),
+            Error::ConnectTimeout => write!(f, "Timed out connecting to any candidate address"),
+            #[cfg(feature = "use-native-tls")]
+            Error::NativeTls(err) => write!(f, "TLS error: {}", err),
+            #[cfg(feature = "use-rustls")]
+            Error::InvalidDnsName(err) => write!(f, "Invalid DNS name: {}", err),
+            Error::Utf8 => write!(f, "UTF-8 encoding error"),
+            Error::Url(msg) => write!(f, "URL error: {}", msg),
+            Error::Http(res) => write!(f, "HTTP error: {}", res.status()),
+            Error::HttpFormat(err) => write!(f, "HTTP format error: {}", err),
+        }
+    }
+}
+
+impl ErrorTrait for Error {
+    fn source(&self) -> Option<&(dyn ErrorTrait + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            #[cfg(feature = "use-native-tls")]
+            Error::NativeTls(err) => Some(err),
+            #[cfg(feature = "use-rustls")]
+            Error::InvalidDnsName(err) => Some(err),
+            Error::HttpFormat(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+#[cfg(feature = "use-native-tls")]
+impl From<NativeTlsError> for Error {
+    fn from(err: NativeTlsError) -> Self {
+        Error::NativeTls(err)
+    }
+}
+
+#[cfg(feature = "use-rustls")]
+impl From<InvalidDNSNameError> for Error {
+    fn from(err: InvalidDNSNameError) -> Self {
+        Error::InvalidDnsName(err)
+    }
+}
+
+impl From<ToStrError> for Error {
+    fn from(_: ToStrError) -> Self {
+        Error::Utf8
+    }
+}
+
+impl From<InvalidUri> for Error {
+    fn from(err: InvalidUri) -> Self {
+        Error::Url(err.to_string().into())
+    }
+}
+
+impl From<http::Error> for Error {
+    fn from(err: http::Error) -> Self {
+        Error::HttpFormat(err)
+    }
+}